@@ -1,16 +1,108 @@
 use std::collections::VecDeque;
 
+use crate::credential::{RunAs, Secret};
 use crate::PsScript;
 
 /// Builds a `PsScript` instance with configurable options for running your
 /// script.
 pub struct PsScriptBuilder {
-    args: VecDeque<&'static str>,
+    args: VecDeque<String>,
+    extra_args: Vec<String>,
     no_profile: bool,
     non_interactive: bool,
     hidden: bool,
     print_commands: bool,
     execution_policy: Option<ExecutionPolicy>,
+    interpreter: Interpreter,
+    input_format: InputFormat,
+    run_as: Option<RunAs>,
+    stop_on_error: bool,
+    output_format: OutputFormat,
+}
+
+/// Controls how a script's result is captured via the `-OutputFormat`
+/// parameter.
+///
+/// `Json` additionally pipes the script body through `ConvertTo-Json`
+/// before it's run, so the captured stdout can be deserialized directly
+/// into a caller-provided type with [`crate::PsOutput::deserialize`]
+/// instead of scraped as a raw string. `-OutputFormat` itself only
+/// understands `Text`/`Xml`, so `Json` is sent as `Text`.
+pub enum OutputFormat {
+    Text,
+    Xml,
+    Json,
+}
+
+impl OutputFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OutputFormat::Text | OutputFormat::Json => "Text",
+            OutputFormat::Xml => "Xml",
+        }
+    }
+
+    pub(crate) fn is_json(&self) -> bool {
+        matches!(self, OutputFormat::Json)
+    }
+}
+
+/// Controls how PowerShell interprets data piped to it over STDIN via the
+/// `-InputFormat` parameter.
+///
+/// The script body is always streamed to the child process over STDIN
+/// (`-Command -`), so without `-InputFormat None` PowerShell can hang
+/// waiting to auto-detect the format of a stream that never closes the way
+/// it expects. `None` is the default for exactly that reason.
+pub enum InputFormat {
+    Text,
+    Xml,
+    None,
+}
+
+impl InputFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            InputFormat::Text => "Text",
+            InputFormat::Xml => "Xml",
+            InputFormat::None => "None",
+        }
+    }
+}
+
+/// Selects which PowerShell binary a `PsScript` is run with.
+///
+/// `powershell.exe` (Windows PowerShell) is only available on Windows, while
+/// `pwsh` (PowerShell 7+, "PowerShell Core") ships on every platform, so the
+/// default is picked based on the target OS. Use `Custom` to point at a
+/// binary under a different name or an explicit path.
+pub enum Interpreter {
+    WindowsPowerShell,
+    PowerShellCore,
+    Custom(String),
+}
+
+impl Interpreter {
+    /// The binary name (or path, for `Custom`) to spawn.
+    pub(crate) fn binary(&self) -> &str {
+        match self {
+            Interpreter::WindowsPowerShell => "powershell.exe",
+            Interpreter::PowerShellCore => "pwsh",
+            Interpreter::Custom(path) => path,
+        }
+    }
+}
+
+impl Default for Interpreter {
+    /// Defaults to `pwsh` everywhere except Windows, where `powershell.exe`
+    /// is assumed to always be present.
+    fn default() -> Self {
+        if cfg!(windows) {
+            Interpreter::WindowsPowerShell
+        } else {
+            Interpreter::PowerShellCore
+        }
+    }
 }
 
 // Possible powershell ExecutionPolicies
@@ -69,33 +161,105 @@ impl PsScriptBuilder {
         self
     }
 
+    /// Selects the PowerShell binary to run the script with. Defaults to
+    /// `pwsh` on non-Windows platforms and `powershell.exe` on Windows.
+    pub fn interpreter(mut self, interpreter: Interpreter) -> Self {
+        self.interpreter = interpreter;
+        self
+    }
+
+    /// Sets the `-InputFormat` passed to PowerShell. Defaults to `None`,
+    /// which avoids hangs caused by PowerShell trying to auto-detect the
+    /// format of the piped STDIN stream.
+    pub fn input_format(mut self, format: InputFormat) -> Self {
+        self.input_format = format;
+        self
+    }
+
+    /// Appends arbitrary, user-supplied flags (e.g. `-WindowStyle`,
+    /// `-EncodedCommand`) to the argument list.
+    ///
+    /// These are placed after all of the builder's own defaults and before
+    /// the trailing `-Command -`, so a flag that duplicates one of the
+    /// built-in defaults (say, a second `-ExecutionPolicy`) takes precedence
+    /// over it: PowerShell parses duplicate flags and keeps the last one it
+    /// sees.
+    pub fn extra_args(mut self, args: impl IntoIterator<Item = String>) -> Self {
+        self.extra_args.extend(args);
+        self
+    }
+
+    /// Runs the script as `domain_user` (e.g. `"DOMAIN\\user"` or
+    /// `"user@domain"`) instead of the current process user, via
+    /// `Start-Process -Credential`.
+    ///
+    /// `password` is never placed on the spawned process's command line; it
+    /// is embedded in the generated credential preamble and streamed to
+    /// PowerShell over STDIN along with the rest of the script.
+    pub fn run_as(mut self, domain_user: impl Into<String>, password: Secret) -> Self {
+        self.run_as = Some(RunAs::new(domain_user, password));
+        self
+    }
+
+    /// If set to `true`, prepends `$ErrorActionPreference = 'Stop'` to the
+    /// script so that non-terminating errors become terminating and are
+    /// surfaced as a non-zero exit instead of being silently swallowed.
+    pub fn stop_on_error(mut self, flag: bool) -> Self {
+        self.stop_on_error = flag;
+        self
+    }
+
+    /// Sets the `-OutputFormat` passed to PowerShell. Defaults to `Text`.
+    ///
+    /// `OutputFormat::Json` additionally pipes the script through
+    /// `ConvertTo-Json` so the result can be deserialized with
+    /// [`crate::PsOutput::deserialize`].
+    pub fn output_format(mut self, format: OutputFormat) -> Self {
+        self.output_format = format;
+        self
+    }
+
     pub fn build(self) -> PsScript {
         let mut args = self.args;
         if self.non_interactive {
-            args.push_front("-NonInteractive");
+            args.push_front("-NonInteractive".to_string());
         }
 
         if self.no_profile {
-            args.push_front("-NoProfile");
+            args.push_front("-NoProfile".to_string());
         }
 
-        if self.execution_policy.is_some(){
-            match self.execution_policy.unwrap() {
-                ExecutionPolicy::AllSigned => args.push_front("AllSigned"),
-                ExecutionPolicy::Bypass => args.push_front("Bypass"),
-                ExecutionPolicy::Default => args.push_front("Default"),
-                ExecutionPolicy::RemoteSigned => args.push_front("RemoteSigned"),
-                ExecutionPolicy::Restricted => args.push_front("Restricted"),
-                ExecutionPolicy::Undefined => args.push_front("Undefined"),
-                ExecutionPolicy::Unrestricted => args.push_front("Unrestricted"),
+        if let Some(execution_policy) = self.execution_policy {
+            match execution_policy {
+                ExecutionPolicy::AllSigned => args.push_front("AllSigned".to_string()),
+                ExecutionPolicy::Bypass => args.push_front("Bypass".to_string()),
+                ExecutionPolicy::Default => args.push_front("Default".to_string()),
+                ExecutionPolicy::RemoteSigned => args.push_front("RemoteSigned".to_string()),
+                ExecutionPolicy::Restricted => args.push_front("Restricted".to_string()),
+                ExecutionPolicy::Undefined => args.push_front("Undefined".to_string()),
+                ExecutionPolicy::Unrestricted => args.push_front("Unrestricted".to_string()),
             }
-			args.push_front("-ExecutionPolicy");
+			args.push_front("-ExecutionPolicy".to_string());
         }
 
+        args.push_front(self.input_format.as_str().to_string());
+        args.push_front("-InputFormat".to_string());
+
+        args.push_front(self.output_format.as_str().to_string());
+        args.push_front("-OutputFormat".to_string());
+
+        args.extend(self.extra_args);
+        args.push_back("-Command".to_string());
+        args.push_back("-".to_string());
+
         PsScript {
-            args: args.make_contiguous().to_vec(),
+            interpreter: self.interpreter,
+            args: args.into(),
             hidden: self.hidden,
             print_commands: self.print_commands,
+            run_as: self.run_as,
+            stop_on_error: self.stop_on_error,
+            output_format: self.output_format,
         }
     }
 }
@@ -105,17 +269,71 @@ impl Default for PsScriptBuilder {
     /// Creates a default builder with `no_profile`, `non_interactive` and `hidden`
     /// options set to `true` and `print_commands` set to `false`.
     fn default() -> Self {
-        let mut args = VecDeque::new();
-        args.push_back("-Command");
-        args.push_back("-");
-
         Self {
-            args,
+            args: VecDeque::new(),
+            extra_args: Vec::new(),
             no_profile: true,
             non_interactive: true,
             hidden: true,
             print_commands: false,
             execution_policy: None,
+            interpreter: Interpreter::default(),
+            input_format: InputFormat::None,
+            run_as: None,
+            stop_on_error: false,
+            output_format: OutputFormat::Text,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpreter_binary_names() {
+        assert_eq!(Interpreter::WindowsPowerShell.binary(), "powershell.exe");
+        assert_eq!(Interpreter::PowerShellCore.binary(), "pwsh");
+        assert_eq!(Interpreter::Custom("/opt/pwsh-beta".to_string()).binary(), "/opt/pwsh-beta");
+    }
+
+    #[test]
+    fn default_args_have_input_format_none_before_command() {
+        let ps = PsScriptBuilder::new().build();
+        assert_eq!(
+            ps.args,
+            vec![
+                "-OutputFormat",
+                "Text",
+                "-InputFormat",
+                "None",
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                "-",
+            ]
+        );
+    }
+
+    #[test]
+    fn extra_args_land_after_defaults_and_before_command() {
+        let ps = PsScriptBuilder::new()
+            .extra_args(vec!["-WindowStyle".to_string(), "Hidden".to_string()])
+            .build();
+        let command_index = ps.args.iter().position(|a| a == "-Command").unwrap();
+        let window_style_index = ps.args.iter().position(|a| a == "-WindowStyle").unwrap();
+        assert!(window_style_index < command_index);
+        assert!(window_style_index > 0);
+    }
+
+    #[test]
+    fn input_format_is_callable_from_outside_the_crate_and_sets_the_flag() {
+        // `crate::InputFormat`, not `crate::builder::InputFormat`, matching
+        // how a downstream caller would spell it.
+        let ps = PsScriptBuilder::new()
+            .input_format(crate::InputFormat::Text)
+            .build();
+        let input_format_index = ps.args.iter().position(|a| a == "-InputFormat").unwrap();
+        assert_eq!(ps.args[input_format_index + 1], "Text");
+    }
+}
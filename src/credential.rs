@@ -0,0 +1,164 @@
+use std::fmt;
+
+/// A password or other sensitive value.
+///
+/// `Secret`'s `Debug` implementation never prints the wrapped value, so it's
+/// safe to include a `Secret` in a struct that derives or implements `Debug`
+/// without accidentally leaking it into logs.
+pub struct Secret(String);
+
+impl Secret {
+    /// Wraps `value` as a secret.
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub(crate) fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(..)")
+    }
+}
+
+/// Credentials used to run a script as another user.
+///
+/// Built via [`crate::PsScriptBuilder::run_as`]; see its doc comment for how
+/// the password is delivered to PowerShell.
+pub struct RunAs {
+    pub(crate) user: String,
+    pub(crate) password: Secret,
+}
+
+impl RunAs {
+    pub(crate) fn new(user: impl Into<String>, password: Secret) -> Self {
+        Self {
+            user: user.into(),
+            password,
+        }
+    }
+
+    /// Wraps `script` so it runs under this credential via
+    /// `Start-Process -Credential`, invoking a fresh `interpreter` process
+    /// to execute the original script body.
+    ///
+    /// `inner_args` are the flags the caller configured on the builder
+    /// (execution policy, extra args, input/output format, ...) and are
+    /// forwarded to that child process verbatim, so they apply to the
+    /// credentialed run the same way they would to an unprivileged one.
+    ///
+    /// The inner script is passed via `-EncodedCommand` rather than
+    /// `-Command`/`-ArgumentList`: `Start-Process -ArgumentList` joins its
+    /// elements with a single unescaped space to build the child's command
+    /// line, so any script containing whitespace (i.e. virtually all of
+    /// them) would otherwise be fragmented into bogus extra arguments.
+    /// Base64-encoding it sidesteps quoting entirely.
+    pub(crate) fn wrap(&self, interpreter: &str, inner_args: &[String], script: &str) -> String {
+        let encoded_command = base64_encode_utf16le(script);
+
+        let mut argument_list: Vec<String> = inner_args
+            .iter()
+            .map(|arg| format!("'{}'", escape_single_quoted(arg)))
+            .collect();
+        argument_list.push("'-EncodedCommand'".to_string());
+        argument_list.push(format!("'{}'", encoded_command));
+
+        format!(
+            "$PsScriptPassword = ConvertTo-SecureString -String '{password}' -AsPlainText -Force\n\
+             $PsScriptCredential = New-Object System.Management.Automation.PSCredential('{user}', $PsScriptPassword)\n\
+             Start-Process -FilePath '{interpreter}' -Credential $PsScriptCredential -ArgumentList @({argument_list}) -NoNewWindow -Wait\n",
+            password = escape_single_quoted(self.password.expose()),
+            user = escape_single_quoted(&self.user),
+            interpreter = interpreter,
+            argument_list = argument_list.join(", "),
+        )
+    }
+}
+
+/// Escapes `value` for embedding in a PowerShell single-quoted string
+/// literal, where the only special character is the quote itself.
+fn escape_single_quoted(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+/// Encodes `script` the way PowerShell's `-EncodedCommand` expects: UTF-16LE
+/// code units, then standard base64.
+fn base64_encode_utf16le(script: &str) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let bytes: Vec<u8> = script
+        .encode_utf16()
+        .flat_map(|unit| unit.to_le_bytes())
+        .collect();
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = *chunk.get(1).unwrap_or(&0);
+        let b2 = *chunk.get(2).unwrap_or(&0);
+
+        encoded.push(ALPHABET[(b0 >> 2) as usize] as char);
+        encoded.push(ALPHABET[(((b0 & 0x03) << 4) | (b1 >> 4)) as usize] as char);
+        encoded.push(if chunk.len() > 1 {
+            ALPHABET[(((b1 & 0x0f) << 2) | (b2 >> 6)) as usize] as char
+        } else {
+            '='
+        });
+        encoded.push(if chunk.len() > 2 {
+            ALPHABET[(b2 & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_single_quoted_doubles_embedded_quotes() {
+        assert_eq!(escape_single_quoted("it's a test"), "it''s a test");
+    }
+
+    #[test]
+    fn base64_encode_utf16le_matches_known_vector() {
+        // "hi" encoded as UTF-16LE then base64, the same
+        // encoding `powershell -EncodedCommand` expects.
+        assert_eq!(base64_encode_utf16le("hi"), "aABpAA==");
+    }
+
+    #[test]
+    fn wrap_passes_script_via_encoded_command_not_argument_list() {
+        let run_as = RunAs::new("user", Secret::new("p@ss w0rd"));
+        let wrapped = run_as.wrap("pwsh", &[], "Get-Process -Name 'note pad'");
+
+        // The whitespace-containing script must never appear as a literal
+        // -ArgumentList element, only as a base64 -EncodedCommand value.
+        assert!(!wrapped.contains("Get-Process"));
+        assert!(wrapped.contains("-EncodedCommand"));
+        assert!(wrapped.contains(&base64_encode_utf16le("Get-Process -Name 'note pad'")));
+    }
+
+    #[test]
+    fn wrap_forwards_inner_args_to_the_credentialed_process() {
+        let run_as = RunAs::new("user", Secret::new("pw"));
+        let inner_args = vec![
+            "-ExecutionPolicy".to_string(),
+            "Bypass".to_string(),
+            "-InputFormat".to_string(),
+            "None".to_string(),
+        ];
+        let wrapped = run_as.wrap("pwsh", &inner_args, "Get-Process");
+
+        assert!(wrapped.contains("'-ExecutionPolicy', 'Bypass'"));
+        assert!(wrapped.contains("'-InputFormat', 'None'"));
+        assert!(wrapped.contains("-EncodedCommand"));
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+
+/// The message PowerShell writes to stderr for a positional-parameter
+/// mismatch; stderr containing it is classified as
+/// [`PsError::BadArgument`].
+const POSITIONAL_PARAMETER_MARKER: &str = "A positional parameter cannot be found that accepts argument";
+
+/// Errors returned by [`crate::PsScript::run`].
+#[derive(Debug)]
+pub enum PsError {
+    /// Spawning the PowerShell process, or communicating with it, failed.
+    Io(std::io::Error),
+    /// PowerShell rejected an argument to the script, most commonly because
+    /// a value containing spaces or special characters wasn't quoted or
+    /// escaped before being spliced into the script text.
+    BadArgument { stderr: String },
+    /// The script exited with a non-zero status for a reason other than a
+    /// bad argument.
+    Terminating { stderr: String },
+    /// The script exited successfully but still wrote to stderr, e.g. a
+    /// non-terminating error or a `Write-Warning` call. Distinct from
+    /// [`PsError::Terminating`]: the script did finish, but its stderr
+    /// shouldn't be silently discarded.
+    Warning { stderr: String },
+    /// Captured output wasn't valid UTF-8.
+    InvalidUtf8(std::string::FromUtf8Error),
+    /// `PsOutput::deserialize` failed to parse the captured stdout as JSON.
+    Deserialize(serde_json::Error),
+}
+
+impl PsError {
+    pub(crate) fn from_stderr(stderr: String) -> Self {
+        if stderr.contains(POSITIONAL_PARAMETER_MARKER) {
+            PsError::BadArgument { stderr }
+        } else {
+            PsError::Terminating { stderr }
+        }
+    }
+}
+
+impl fmt::Display for PsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PsError::Io(err) => write!(f, "failed to run powershell: {}", err),
+            PsError::BadArgument { stderr } => write!(
+                f,
+                "powershell rejected an argument, check that values containing \
+                 spaces or special characters are quoted or escaped: {}",
+                stderr
+            ),
+            PsError::Terminating { stderr } => {
+                write!(f, "powershell script exited with an error: {}", stderr)
+            }
+            PsError::Warning { stderr } => {
+                write!(f, "powershell script wrote to stderr: {}", stderr)
+            }
+            PsError::InvalidUtf8(err) => write!(f, "captured output was not valid UTF-8: {}", err),
+            PsError::Deserialize(err) => {
+                write!(f, "failed to deserialize script output as JSON: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PsError {}
+
+impl From<std::io::Error> for PsError {
+    fn from(err: std::io::Error) -> Self {
+        PsError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positional_parameter_mismatch_is_a_bad_argument() {
+        let stderr = "A positional parameter cannot be found that accepts argument 'foo'."
+            .to_string();
+        assert!(matches!(
+            PsError::from_stderr(stderr),
+            PsError::BadArgument { .. }
+        ));
+    }
+
+    #[test]
+    fn other_stderr_is_terminating() {
+        let stderr = "Get-Item: Cannot find path 'C:\\missing' because it does not exist."
+            .to_string();
+        assert!(matches!(
+            PsError::from_stderr(stderr),
+            PsError::Terminating { .. }
+        ));
+    }
+}
@@ -0,0 +1,133 @@
+//! A small wrapper around spawning PowerShell and piping a script to it.
+//!
+//! ```no_run
+//! use powershell_script::PsScriptBuilder;
+//!
+//! let ps = PsScriptBuilder::new().build();
+//! let output = ps.run(r#"Write-Host "hello world""#).unwrap();
+//! println!("{}", output.stdout().unwrap());
+//! ```
+
+mod builder;
+mod credential;
+mod error;
+mod output;
+
+pub use builder::{ExecutionPolicy, InputFormat, Interpreter, OutputFormat, PsScriptBuilder};
+pub use credential::Secret;
+pub use error::PsError;
+pub use output::PsOutput;
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::credential::RunAs;
+
+/// A configured, ready-to-run PowerShell invocation produced by
+/// [`PsScriptBuilder::build`].
+pub struct PsScript {
+    pub(crate) interpreter: Interpreter,
+    pub(crate) args: Vec<String>,
+    // Only consulted on Windows; see `PsScriptBuilder::hidden`'s no-op note
+    // for other platforms.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub(crate) hidden: bool,
+    pub(crate) print_commands: bool,
+    pub(crate) run_as: Option<RunAs>,
+    pub(crate) stop_on_error: bool,
+    pub(crate) output_format: OutputFormat,
+}
+
+impl PsScript {
+    /// Runs `script` through the configured PowerShell interpreter and waits
+    /// for it to finish, returning the captured output.
+    ///
+    /// Returns `Err` if the process couldn't be spawned, or if it exited
+    /// with a non-zero status; the error is classified from stderr, e.g. as
+    /// [`PsError::BadArgument`] for a positional-parameter mismatch. A
+    /// clean exit with non-empty stderr is also an `Err`, as
+    /// [`PsError::Warning`], so a script that wrote to stderr without
+    /// failing can't be mistaken for one that ran silently.
+    pub fn run(&self, script: &str) -> Result<PsOutput, PsError> {
+        let mut script = if self.output_format.is_json() {
+            wrap_for_json(script)
+        } else {
+            script.to_string()
+        };
+
+        if self.stop_on_error {
+            script = format!("$ErrorActionPreference = 'Stop'\n{}", script);
+        }
+
+        // Printed before `run_as` wrapping: that wrapping embeds the
+        // RunAs password in plaintext, which must never hit stdout/logs.
+        if self.print_commands {
+            println!("{}", script);
+        }
+
+        if let Some(run_as) = &self.run_as {
+            // `self.args` always ends in the trailing `-Command -` used to
+            // stream the script below; the credentialed child instead gets
+            // the script via `-EncodedCommand`, so that pair is dropped and
+            // everything else (execution policy, extra args, input/output
+            // format, ...) is forwarded as-is.
+            let inner_args = &self.args[..self.args.len() - 2];
+            script = run_as.wrap(self.interpreter.binary(), inner_args, &script);
+        }
+
+        let mut command = Command::new(self.interpreter.binary());
+        command
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(windows)]
+        if self.hidden {
+            use std::os::windows::process::CommandExt;
+            // CREATE_NO_WINDOW
+            command.creation_flags(0x08000000);
+        }
+
+        let mut child = command.spawn()?;
+        child
+            .stdin
+            .take()
+            .expect("stdin was piped")
+            .write_all(script.as_bytes())?;
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+            return Err(PsError::from_stderr(stderr));
+        }
+
+        if !output.stderr.is_empty() {
+            let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+            return Err(PsError::Warning { stderr });
+        }
+
+        Ok(PsOutput::from(output))
+    }
+}
+
+/// Wraps `script` in a script block before piping it through
+/// `ConvertTo-Json`, so every statement's output is converted rather than
+/// just the last one.
+fn wrap_for_json(script: &str) -> String {
+    format!("& {{\n{}\n}} | ConvertTo-Json -Compress", script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_for_json_converts_the_whole_script_block() {
+        let wrapped = wrap_for_json("Get-Process\nGet-Service");
+        assert_eq!(
+            wrapped,
+            "& {\nGet-Process\nGet-Service\n} | ConvertTo-Json -Compress"
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use std::process::Output;
+
+use serde::de::DeserializeOwned;
+
+use crate::PsError;
+
+/// The captured result of running a [`crate::PsScript`].
+pub struct PsOutput {
+    success: bool,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+impl PsOutput {
+    /// Whether the PowerShell process exited successfully.
+    pub fn success(&self) -> bool {
+        self.success
+    }
+
+    /// The captured `stdout`, as UTF-8.
+    pub fn stdout(&self) -> Result<String, std::string::FromUtf8Error> {
+        String::from_utf8(self.stdout.clone())
+    }
+
+    /// The captured `stderr`, as UTF-8.
+    pub fn stderr(&self) -> Result<String, std::string::FromUtf8Error> {
+        String::from_utf8(self.stderr.clone())
+    }
+
+    /// Deserializes `stdout` into `T`.
+    ///
+    /// Only meaningful when the script was built with
+    /// [`crate::OutputFormat::Json`], which pipes the script's result
+    /// through `ConvertTo-Json` so it can be captured here as plain JSON
+    /// rather than PowerShell's formatted text output.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, PsError> {
+        let stdout = self.stdout().map_err(PsError::InvalidUtf8)?;
+        serde_json::from_str(&stdout).map_err(PsError::Deserialize)
+    }
+}
+
+impl From<Output> for PsOutput {
+    fn from(output: Output) -> Self {
+        Self {
+            success: output.status.success(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        }
+    }
+}